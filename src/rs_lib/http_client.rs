@@ -16,6 +16,53 @@ use wasm_bindgen::JsValue;
 #[wasm_bindgen(module = "/helpers.js")]
 extern "C" {
   async fn fetch_specifier(specifier: String, headers: JsValue) -> JsValue;
+  async fn delay(ms: f64);
+}
+
+/// Maximum number of attempts (including the first) for a single fetch.
+const MAX_FETCH_ATTEMPTS: u32 = 3;
+/// Base delay for the exponential backoff, doubled on each retry.
+const BASE_RETRY_DELAY_MS: f64 = 250.0;
+
+fn is_retriable_status(status: u16) -> bool {
+  (500..=599).contains(&status)
+}
+
+/// Sleeps for an exponentially increasing delay (plus jitter) before a retry.
+/// `attempt` is 1-based: the delay before the 2nd attempt, before the 3rd, etc.
+async fn backoff_delay(attempt: u32) {
+  let base = BASE_RETRY_DELAY_MS * 2f64.powi(attempt as i32 - 1);
+  let jitter = base * js_sys::Math::random() * 0.25;
+  delay(base + jitter).await;
+}
+
+/// Wraps `fetch_specifier_typed` with retries: network/send failures and
+/// 5xx responses are retried with exponential backoff, but 404, 304, and
+/// other 4xx responses are returned immediately since retrying won't help.
+async fn fetch_specifier_with_retries(
+  specifier: &str,
+  headers: Vec<(String, String)>,
+) -> Result<FetchResult, anyhow::Error> {
+  let mut attempt = 0;
+  loop {
+    attempt += 1;
+    match fetch_specifier_typed(specifier, headers.clone()).await {
+      Ok(FetchResult::Response(response))
+        if is_retriable_status(response.status)
+          && attempt < MAX_FETCH_ATTEMPTS =>
+      {
+        backoff_delay(attempt).await;
+      }
+      Ok(FetchResult::Error(_)) if attempt < MAX_FETCH_ATTEMPTS => {
+        backoff_delay(attempt).await;
+      }
+      Ok(result) => return Ok(result),
+      Err(_err) if attempt < MAX_FETCH_ATTEMPTS => {
+        backoff_delay(attempt).await;
+      }
+      Err(err) => return Err(err),
+    }
+  }
 }
 
 enum FetchResult {
@@ -65,7 +112,7 @@ impl deno_cache_dir::file_fetcher::HttpClient for WasmHttpClient {
       .into_iter()
       .filter_map(|(k, v)| Some((k?.to_string(), v.to_str().ok()?.to_string())))
       .collect::<Vec<(String, String)>>();
-    let result = fetch_specifier_typed(url.as_str(), headers)
+    let result = fetch_specifier_with_retries(url.as_str(), headers)
       .await
       .map_err(|err| {
         SendError::Failed(Box::new(std::io::Error::new(
@@ -96,7 +143,6 @@ impl deno_cache_dir::file_fetcher::HttpClient for WasmHttpClient {
 
 #[async_trait::async_trait(?Send)]
 impl deno_npm_cache::NpmCacheHttpClient for WasmHttpClient {
-  // todo: implement retrying
   async fn download_with_retries_on_any_tokio_runtime(
     &self,
     url: Url,
@@ -111,7 +157,7 @@ impl deno_npm_cache::NpmCacheHttpClient for WasmHttpClient {
       headers.push(("if-none-match".to_string(), etag));
     }
 
-    let result = fetch_specifier_typed(url.as_str(), headers)
+    let result = fetch_specifier_with_retries(url.as_str(), headers)
       .await
       .map_err(|err| deno_npm_cache::DownloadError {
         status_code: None,
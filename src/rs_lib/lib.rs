@@ -1,11 +1,15 @@
 mod http_client;
 mod module_analyzer;
 
+use std::cell::RefCell;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 
 use deno_cache_dir::file_fetcher::CacheSetting;
+use deno_cache_dir::file_fetcher::HeaderMap;
+use deno_cache_dir::file_fetcher::HeaderName;
+use deno_cache_dir::file_fetcher::HeaderValue;
 use deno_cache_dir::file_fetcher::NullBlobStore;
 use deno_graph::MediaType;
 use deno_graph::Module;
@@ -21,6 +25,8 @@ use deno_resolver::file_fetcher::DenoGraphLoaderOptions;
 use deno_resolver::file_fetcher::PermissionedFileFetcher;
 use deno_resolver::file_fetcher::PermissionedFileFetcherOptions;
 use deno_resolver::graph::DefaultDenoResolverRc;
+use deno_resolver::sloppy_imports::SloppyImportsResolutionMode;
+use deno_resolver::sloppy_imports::SloppyImportsResolver;
 use deno_resolver::workspace::ScopedJsxImportSourceConfig;
 use serde::Serialize;
 use sys_traits::impls::RealSys;
@@ -46,18 +52,26 @@ pub struct DenoPlugin {
   file_fetcher:
     Arc<PermissionedFileFetcher<NullBlobStore, RealSys, WasmHttpClient>>,
   graph: ModuleGraph,
+  sloppy_imports_resolver: Option<Arc<SloppyImportsResolver<RealSys>>>,
+  warnings: RefCell<Vec<String>>,
 }
 
 #[wasm_bindgen]
 impl DenoPlugin {
-  pub async fn create(entrypoints: Vec<String>) -> Result<Self, String> {
+  pub async fn create(
+    entrypoints: Vec<String>,
+    sloppy_imports: bool,
+  ) -> Result<Self, String> {
     console_error_panic_hook::set_once();
-    DenoPlugin::create_inner(entrypoints)
+    DenoPlugin::create_inner(entrypoints, sloppy_imports)
       .await
       .map_err(|err| err.to_string())
   }
 
-  async fn create_inner(entrypoints: Vec<String>) -> Result<Self, anyhow::Error> {
+  async fn create_inner(
+    entrypoints: Vec<String>,
+    sloppy_imports: bool,
+  ) -> Result<Self, anyhow::Error> {
     let sys = RealSys;
     let cwd = sys.env_current_dir()?;
     let roots = entrypoints.iter().map(|e| parse_entrypoint(e, &cwd)).collect::<Result<Vec<_>, _>>()?;
@@ -69,10 +83,15 @@ impl DenoPlugin {
       ResolverFactoryOptions {
         is_cjs_resolution_mode:
           deno_resolver::cjs::IsCjsResolutionMode::ImplicitTypeCommonJs,
-        unstable_sloppy_imports: true,
+        unstable_sloppy_imports: sloppy_imports,
         ..Default::default()
       },
     ));
+    let sloppy_imports_resolver = if sloppy_imports {
+      Some(Arc::new(SloppyImportsResolver::new(sys.clone())))
+    } else {
+      None
+    };
     let wasm_http_client = WasmHttpClient::default();
     let npm_installer_factory = NpmInstallerFactory::new(
       resolver_factory.clone(),
@@ -162,9 +181,18 @@ impl DenoPlugin {
       file_fetcher,
       resolver: resolver.clone(),
       graph,
+      sloppy_imports_resolver,
+      warnings: RefCell::new(Vec::new()),
     })
   }
 
+  /// Drains and returns the resolution warnings collected so far (e.g. hints
+  /// about specifiers that only resolved because sloppy imports is enabled).
+  pub fn take_warnings(&self) -> Result<JsValue, String> {
+    let warnings = std::mem::take(&mut *self.warnings.borrow_mut());
+    serde_wasm_bindgen::to_value(&warnings).map_err(|err| err.to_string())
+  }
+
   pub fn resolve(
     &self,
     specifier: String,
@@ -186,8 +214,11 @@ impl DenoPlugin {
     importer: Option<String>,
     resolution_mode: node_resolver::ResolutionMode,
   ) -> Result<String, anyhow::Error> {
+    if specifier.starts_with("data:") {
+      return Ok(Url::parse(&specifier)?.to_string());
+    }
     let referrer = match &importer {
-      Some(referrer) if referrer.starts_with("http:") || referrer.starts_with("https:") || referrer.starts_with("file:") => Url::parse(referrer)?,
+      Some(referrer) if referrer.starts_with("http:") || referrer.starts_with("https:") || referrer.starts_with("file:") || referrer.starts_with("data:") => Url::parse(referrer)?,
       Some(referrer) => deno_path_util::url_from_file_path(&PathBuf::from(referrer))?,
       None => {
         return Ok(parse_entrypoint(&specifier, &self.cwd)?.to_string())
@@ -201,9 +232,44 @@ impl DenoPlugin {
       resolution_mode,
       node_resolver::NodeResolutionKind::Execution,
     )?;
+    self.record_sloppy_imports_warning(&specifier, &referrer, &resolved);
     Ok(resolved.to_string())
   }
 
+  /// When sloppy imports is enabled and `specifier` could only have resolved
+  /// via extension probing, directory-index probing, or `.js` -> `.ts`
+  /// remapping, records a warning suggesting the explicit specifier. Delegates
+  /// to `SloppyImportsResolver` for the actual probing rules rather than
+  /// re-deriving them from string comparison, so this stays in sync with
+  /// deno_resolver's own sloppy-imports behavior.
+  fn record_sloppy_imports_warning(
+    &self,
+    specifier: &str,
+    referrer: &Url,
+    resolved: &Url,
+  ) {
+    let Some(resolver) = &self.sloppy_imports_resolver else {
+      return;
+    };
+    if !(specifier.starts_with("./") || specifier.starts_with("../")) {
+      return;
+    }
+    let Ok(naive) = referrer.join(specifier) else {
+      return;
+    };
+    let Some(resolution) =
+      resolver.resolve(&naive, SloppyImportsResolutionMode::Execution)
+    else {
+      return;
+    };
+    let Some(suggestion_message) = resolution.as_suggestion_message() else {
+      return;
+    };
+    self.warnings.borrow_mut().push(format!(
+      "\"{specifier}\" resolved to \"{resolved}\" only because sloppy imports is enabled; {suggestion_message}",
+    ));
+  }
+
   pub async fn load(&self, url: String) -> Result<JsValue, String> {
     let response = self.load_inner(url).await.map_err(|err| err.to_string())?;
     let value =
@@ -217,6 +283,10 @@ impl DenoPlugin {
   ) -> Result<Option<LoadResponse>, anyhow::Error> {
     let url = Url::parse(&url)?;
 
+    if url.scheme() == "data" {
+      return Ok(Some(load_data_url(&url)?));
+    }
+
     match self.graph.get(&url) {
       Some(Module::Js(js)) => Ok(Some(LoadResponse {
         specifier: js.specifier.to_string(),
@@ -250,6 +320,7 @@ fn parse_entrypoint(entrypoint: &str, cwd:& Path) -> Result<Url, anyhow::Error>
   if entrypoint.starts_with("jsr:")
       || entrypoint.starts_with("https:")
       || entrypoint.starts_with("file:")
+      || entrypoint.starts_with("data:")
     {
       Ok(Url::parse(&entrypoint)?)
     } else {
@@ -257,6 +328,41 @@ fn parse_entrypoint(entrypoint: &str, cwd:& Path) -> Result<Url, anyhow::Error>
     }
 }
 
+/// Parses a `data:[<mediatype>][;base64],<payload>` URL into its declared
+/// mime type and decoded UTF-8 source text. Delegates to `RawDataUrl` rather
+/// than slicing `Url::path()` ourselves, since `path()` truncates at a
+/// literal `#`/`?` in the payload even though those are just regular data
+/// bytes for the `data:` scheme.
+fn parse_data_url(url: &Url) -> Result<(String, String), anyhow::Error> {
+  let (bytes, mime) = deno_media_type::data_url::RawDataUrl::parse(url)
+    .map_err(|err| anyhow::anyhow!(err.to_string()))?
+    .decode()
+    .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+  let mime = if mime.is_empty() {
+    "text/javascript".to_string()
+  } else {
+    mime
+  };
+  Ok((mime, String::from_utf8(bytes)?))
+}
+
+fn media_type_from_mime(url: &Url, mime: &str) -> MediaType {
+  let mut headers = HeaderMap::new();
+  if let Ok(value) = HeaderValue::from_str(mime) {
+    headers.insert(HeaderName::from_static("content-type"), value);
+  }
+  MediaType::from_specifier_and_headers(url, Some(&headers))
+}
+
+fn load_data_url(url: &Url) -> Result<LoadResponse, anyhow::Error> {
+  let (mime, code) = parse_data_url(url)?;
+  Ok(LoadResponse {
+    specifier: url.to_string(),
+    media_type: media_type_to_u8(media_type_from_mime(url, &mime)),
+    code,
+  })
+}
+
 fn media_type_to_u8(media_type: MediaType) -> u8 {
   match media_type {
     MediaType::JavaScript => 0,
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use deno_error::JsErrorBox;
@@ -6,11 +7,14 @@ use deno_graph::analysis::DynamicArgument;
 use deno_graph::analysis::DynamicDependencyDescriptor;
 use deno_graph::analysis::DynamicDependencyKind;
 use deno_graph::analysis::DynamicTemplatePart;
+use deno_graph::analysis::ImportAttribute;
 use deno_graph::analysis::ImportAttributes;
 use deno_graph::analysis::ModuleAnalyzer;
 use deno_graph::analysis::ModuleInfo;
+use deno_graph::analysis::SpecifierWithRange;
 use deno_graph::analysis::StaticDependencyDescriptor;
 use deno_graph::analysis::StaticDependencyKind;
+use deno_graph::analysis::TypeScriptReference;
 use deno_graph::MediaType;
 use deno_graph::ModuleSpecifier;
 use deno_graph::Position;
@@ -19,11 +23,17 @@ use oxc::allocator::Allocator;
 use oxc::ast::ast::ExportAllDeclaration;
 use oxc::ast::ast::ExportNamedDeclaration;
 use oxc::ast::ast::Expression;
+use oxc::ast::ast::ImportAttributeKey;
 use oxc::ast::ast::ImportDeclaration;
 use oxc::ast::ast::ImportExpression;
+use oxc::ast::ast::ObjectExpression;
+use oxc::ast::ast::ObjectPropertyKind;
+use oxc::ast::ast::PropertyKey;
+use oxc::ast::ast::WithClause;
 use oxc::ast_visit::walk::walk_program;
 use oxc::ast_visit::Visit;
 use oxc::parser::Parser;
+use oxc::span::GetSpan;
 use oxc::span::SourceType;
 use oxc::span::Span;
 
@@ -80,20 +90,58 @@ impl ModuleAnalyzer for OxcModuleAnalyzer {
     };
     let parser = Parser::new(&allocator, &source_text, source_type);
     let parser_return = parser.parse();
+    let comments: Vec<Span> =
+      parser_return.program.comments.iter().map(|comment| comment.span).collect();
+    let line_index = LineIndex::new(&source_text);
 
     let mut visitor = DependencyCollector {
       source_text: &source_text,
+      line_index: &line_index,
+      comments: &comments,
       dependencies: Vec::new(),
     };
     walk_program(&mut visitor, &parser_return.program);
 
+    let (jsx_import_source, jsx_import_source_types) =
+      if matches!(media_type, MediaType::Jsx | MediaType::Tsx) {
+        let first_statement_start = parser_return
+          .program
+          .body
+          .first()
+          .map_or(source_text.len() as u32, |stmt| stmt.span().start);
+        let leading_comments: Vec<Span> = comments
+          .iter()
+          .copied()
+          .filter(|comment| comment.end <= first_statement_start)
+          .collect();
+        (
+          find_jsx_pragma(
+            &source_text,
+            &line_index,
+            &leading_comments,
+            "@jsxImportSource",
+          ),
+          find_jsx_pragma(
+            &source_text,
+            &line_index,
+            &leading_comments,
+            "@jsxImportSourceTypes",
+          ),
+        )
+      } else {
+        (None, None)
+      };
+
     Ok(ModuleInfo {
       is_script: parser_return.program.source_type.is_script(),
       dependencies: visitor.dependencies,
-      jsx_import_source: Default::default(),
-      // not needed for bundling
-      jsx_import_source_types: Default::default(),
-      ts_references: Default::default(),
+      jsx_import_source,
+      jsx_import_source_types,
+      ts_references: collect_ts_references(
+        &source_text,
+        &line_index,
+        &comments,
+      ),
       self_types_specifier: Default::default(),
       jsdoc_imports: Default::default(),
     })
@@ -102,21 +150,37 @@ impl ModuleAnalyzer for OxcModuleAnalyzer {
 
 struct DependencyCollector<'a> {
   source_text: &'a str,
+  line_index: &'a LineIndex,
+  comments: &'a [Span],
   dependencies: Vec<DependencyDescriptor>,
 }
 
+impl<'a> DependencyCollector<'a> {
+  fn types_specifier_for(&self, node_start: u32) -> Option<SpecifierWithRange> {
+    find_types_specifier(
+      self.source_text,
+      self.line_index,
+      self.comments,
+      node_start,
+    )
+  }
+
+  fn position_range(&self, span: Span) -> PositionRange {
+    span_to_position_range(self.source_text, self.line_index, span)
+  }
+}
+
 impl<'a> Visit<'_> for DependencyCollector<'a> {
   fn visit_import_declaration(&mut self, node: &ImportDeclaration) {
     self.dependencies.push(DependencyDescriptor::Static(
       StaticDependencyDescriptor {
         kind: StaticDependencyKind::Import,
         specifier: node.source.value.to_string(),
-        specifier_range: span_to_position_range(
-          self.source_text,
-          node.source.span,
+        specifier_range: self.position_range(node.source.span),
+        types_specifier: self.types_specifier_for(node.span.start),
+        import_attributes: import_attributes_from_with_clause(
+          node.with_clause.as_deref(),
         ),
-        types_specifier: None,
-        import_attributes: ImportAttributes::default(),
       },
     ));
   }
@@ -127,12 +191,11 @@ impl<'a> Visit<'_> for DependencyCollector<'a> {
         StaticDependencyDescriptor {
           kind: StaticDependencyKind::Export,
           specifier: source.value.to_string(),
-          specifier_range: span_to_position_range(
-            self.source_text,
-            source.span,
+          specifier_range: self.position_range(source.span),
+          types_specifier: self.types_specifier_for(node.span.start),
+          import_attributes: import_attributes_from_with_clause(
+            node.with_clause.as_deref(),
           ),
-          types_specifier: None,
-          import_attributes: ImportAttributes::default(),
         },
       ));
     }
@@ -143,12 +206,11 @@ impl<'a> Visit<'_> for DependencyCollector<'a> {
       StaticDependencyDescriptor {
         kind: StaticDependencyKind::Export,
         specifier: node.source.value.to_string(),
-        specifier_range: span_to_position_range(
-          self.source_text,
-          node.source.span,
+        specifier_range: self.position_range(node.source.span),
+        types_specifier: self.types_specifier_for(node.span.start),
+        import_attributes: import_attributes_from_with_clause(
+          node.with_clause.as_deref(),
         ),
-        types_specifier: None,
-        import_attributes: ImportAttributes::default(),
       },
     ));
   }
@@ -157,7 +219,7 @@ impl<'a> Visit<'_> for DependencyCollector<'a> {
     let (argument, argument_range) = match &node.source {
       Expression::StringLiteral(lit) => (
         DynamicArgument::String(lit.value.to_string()),
-        span_to_position_range(self.source_text, lit.span),
+        self.position_range(lit.span),
       ),
       Expression::TemplateLiteral(tpl) => {
         let mut parts = Vec::new();
@@ -176,53 +238,383 @@ impl<'a> Visit<'_> for DependencyCollector<'a> {
         }
         (
           DynamicArgument::Template(parts),
-          span_to_position_range(self.source_text, tpl.span),
+          self.position_range(tpl.span),
         )
       }
       _ => (
         DynamicArgument::Expr,
-        span_to_position_range(self.source_text, node.span),
+        self.position_range(node.span),
       ),
     };
 
+    let types_specifier = self.types_specifier_for(node.span.start);
+    let import_attributes =
+      import_attributes_from_dynamic_options(node.options.as_ref());
     self.dependencies.push(DependencyDescriptor::Dynamic(
       DynamicDependencyDescriptor {
         kind: DynamicDependencyKind::Import,
         argument,
         argument_range,
-        // todo...
-        import_attributes: ImportAttributes::default(),
-        types_specifier: None,
+        import_attributes,
+        types_specifier,
       },
     ));
   }
 }
 
-fn span_to_position_range(source: &str, span: Span) -> PositionRange {
-  PositionRange {
-    start: byte_index_to_position(source, span.start),
-    end: byte_index_to_position(source, span.end),
+/// Converts a static import/export's `with { ... }` clause into
+/// `ImportAttributes`. Attribute values in a `with_clause` are always
+/// string literals per spec, so this never needs to fall back to `Unknown`.
+fn import_attributes_from_with_clause(
+  with_clause: Option<&WithClause>,
+) -> ImportAttributes {
+  let Some(with_clause) = with_clause else {
+    return ImportAttributes::None;
+  };
+  let mut attributes = HashMap::new();
+  for entry in &with_clause.with_entries {
+    let key = match &entry.key {
+      ImportAttributeKey::Identifier(ident) => ident.name.to_string(),
+      ImportAttributeKey::StringLiteral(lit) => lit.value.to_string(),
+    };
+    attributes.insert(key, ImportAttribute::Known(entry.value.value.to_string()));
+  }
+  ImportAttributes::Known(attributes)
+}
+
+/// Converts a dynamic `import(specifier, { with: { ... } })` options
+/// argument into `ImportAttributes`. Unlike static import attributes, the
+/// options bag is a regular object expression, so a non-literal attribute
+/// value (or a non-literal options bag) is reported as `Unknown` since we
+/// can't determine its value statically.
+fn import_attributes_from_dynamic_options(
+  options: Option<&Expression>,
+) -> ImportAttributes {
+  // No second argument at all: we know statically there are no attributes.
+  let Some(options) = options else {
+    return ImportAttributes::None;
+  };
+  // A non-literal options bag (e.g. an identifier or conditional
+  // expression): we can't tell what it contains.
+  let Expression::ObjectExpression(options) = options else {
+    return ImportAttributes::Unknown;
+  };
+  let attributes_obj = match find_with_property(options) {
+    Ok(Some(obj)) => obj,
+    Ok(None) => return ImportAttributes::None,
+    Err(()) => return ImportAttributes::Unknown,
+  };
+  let mut attributes = HashMap::new();
+  for property in &attributes_obj.properties {
+    let ObjectPropertyKind::ObjectProperty(property) = property else {
+      return ImportAttributes::Unknown;
+    };
+    let Some(key) = object_property_key_name(&property.key) else {
+      return ImportAttributes::Unknown;
+    };
+    match &property.value {
+      Expression::StringLiteral(lit) => {
+        attributes.insert(key, ImportAttribute::Known(lit.value.to_string()));
+      }
+      _ => return ImportAttributes::Unknown,
+    }
+  }
+  ImportAttributes::Known(attributes)
+}
+
+/// Looks for a `with`/`assert` property on `options`.
+/// - `Ok(Some(obj))`: found, and its value is an object literal.
+/// - `Ok(None)`: statically known not to be present.
+/// - `Err(())`: present but not an object literal, or the object can't be
+///   fully inspected statically (e.g. it has a spread property), so whether
+///   a `with`/`assert` key exists can't be determined.
+fn find_with_property<'o>(
+  options: &'o ObjectExpression,
+) -> Result<Option<&'o ObjectExpression<'o>>, ()> {
+  for property in &options.properties {
+    let ObjectPropertyKind::ObjectProperty(property) = property else {
+      // A spread property could introduce a `with`/`assert` key.
+      return Err(());
+    };
+    let Some(key) = object_property_key_name(&property.key) else {
+      return Err(());
+    };
+    if key != "with" && key != "assert" {
+      continue;
+    }
+    return match &property.value {
+      Expression::ObjectExpression(obj) => Ok(Some(obj.as_ref())),
+      _ => Err(()),
+    };
+  }
+  Ok(None)
+}
+
+fn object_property_key_name(key: &PropertyKey) -> Option<String> {
+  match key {
+    PropertyKey::StaticIdentifier(ident) => Some(ident.name.to_string()),
+    PropertyKey::StringLiteral(lit) => Some(lit.value.to_string()),
+    _ => None,
+  }
+}
+
+/// Finds the `@deno-types="..."` or `@ts-types="..."` directive in the
+/// comment directly preceding `node_start`, if any.
+fn find_types_specifier(
+  source: &str,
+  line_index: &LineIndex,
+  comments: &[Span],
+  node_start: u32,
+) -> Option<SpecifierWithRange> {
+  let comment = find_preceding_comment(comments, node_start, source)?;
+  let (text, range) = parse_types_directive(source, line_index, comment)?;
+  Some(SpecifierWithRange { text, range })
+}
+
+/// Returns the closest comment ending before `node_start`, as long as only
+/// whitespace (and at most one line break) separates it from `node_start` —
+/// i.e. the comment sits directly on the line preceding the statement.
+fn find_preceding_comment(
+  comments: &[Span],
+  node_start: u32,
+  source: &str,
+) -> Option<Span> {
+  let comment = *comments.iter().rev().find(|c| c.end <= node_start)?;
+  let between = &source[comment.end as usize..node_start as usize];
+  if between.chars().all(char::is_whitespace)
+    && between.matches('\n').count() <= 1
+  {
+    Some(comment)
+  } else {
+    None
   }
 }
 
-// todo: this is bad
-fn byte_index_to_position(source: &str, index: u32) -> Position {
-  let index = index as usize;
-  let mut line = 0;
-  let mut last_line_start = 0;
+/// Looks for a `@deno-types=` or `@ts-types=` marker inside a single comment
+/// and extracts its quoted value along with the value's `PositionRange`.
+fn parse_types_directive(
+  source: &str,
+  line_index: &LineIndex,
+  comment: Span,
+) -> Option<(String, PositionRange)> {
+  let text = &source[comment.start as usize..comment.end as usize];
+  for (content_offset, content) in comment_line_contents(text) {
+    for marker in ["@deno-types=", "@ts-types="] {
+      if !content.starts_with(marker) {
+        continue;
+      }
+      let quote_idx = content_offset + marker.len();
+      let (value, value_start) =
+        extract_quoted_value(text, comment.start as usize, quote_idx)?;
+      let value_end = value_start + value.len();
+      return Some((
+        value,
+        PositionRange {
+          start: line_index.position(source, value_start),
+          end: line_index.position(source, value_end),
+        },
+      ));
+    }
+  }
+  None
+}
+
+/// Finds a `@jsxImportSource <pkg>` or `@jsxImportSourceTypes <pkg>` pragma
+/// (passed as `marker`, including the leading `@`) inside a leading `/** ...
+/// */` block comment.
+fn find_jsx_pragma(
+  source: &str,
+  line_index: &LineIndex,
+  comments: &[Span],
+  marker: &str,
+) -> Option<SpecifierWithRange> {
+  for &comment in comments {
+    let text = &source[comment.start as usize..comment.end as usize];
+    if !text.starts_with("/*") {
+      continue;
+    }
+    if let Some((value, value_start, value_end)) =
+      find_pragma_value(text, marker)
+    {
+      let value_start = comment.start as usize + value_start;
+      let value_end = comment.start as usize + value_end;
+      return Some(SpecifierWithRange {
+        text: value,
+        range: PositionRange {
+          start: line_index.position(source, value_start),
+          end: line_index.position(source, value_end),
+        },
+      });
+    }
+  }
+  None
+}
 
-  for (i, b) in source.bytes().enumerate() {
-    if i == index {
-      break;
+/// Finds `marker` anchored to the start of a line within `text` (after
+/// leading whitespace, comment delimiters and `*`s, as yielded by
+/// `comment_line_contents`, similar to deno_graph's own `^[\s*]*marker`
+/// pragma regexes) followed by whitespace
+/// and a bare (unquoted) value, e.g. `@jsxImportSource preact`. Skips
+/// occurrences where `marker` is itself a prefix of a longer pragma name
+/// (e.g. `@jsxImportSource` is a prefix of `@jsxImportSourceTypes`).
+fn find_pragma_value(
+  text: &str,
+  marker: &str,
+) -> Option<(String, usize, usize)> {
+  for (content_offset, content) in comment_line_contents(text) {
+    if !content.starts_with(marker) {
+      continue;
     }
-    if b == b'\n' {
-      line += 1;
-      last_line_start = i + 1;
+    let after_marker = content_offset + marker.len();
+    let after = &text[after_marker..];
+    if after.starts_with(|c: char| c.is_alphanumeric()) {
+      continue;
     }
+    let trimmed = after.trim_start_matches([' ', '\t']);
+    let value_len = trimmed
+      .find(|c: char| c.is_whitespace() || c == '*' || c == '/')
+      .unwrap_or(trimmed.len());
+    if value_len == 0 {
+      continue;
+    }
+    let value_start = after_marker + (after.len() - trimmed.len());
+    let value_end = value_start + value_len;
+    return Some((text[value_start..value_end].to_string(), value_start, value_end));
   }
+  None
+}
 
-  Position {
-    line,
-    character: index - last_line_start,
+/// Iterates over the lines of a comment's `text`, yielding for each line the
+/// byte offset (within `text`) where the line's content begins and the
+/// content itself — i.e. after any leading whitespace, the comment's own
+/// opening `//`, `///`, `/*` or `/**` delimiter, and any leading `*` (as on
+/// a JSDoc continuation line). Used to anchor pragma and
+/// directive matches to the start of a line rather than matching `marker`
+/// anywhere in the comment body, which would false-positive on ordinary
+/// prose that happens to contain the marker text.
+fn comment_line_contents(text: &str) -> impl Iterator<Item = (usize, &str)> {
+  let mut line_start = 0;
+  text.split_inclusive('\n').map(move |line| {
+    let start = line_start;
+    line_start += line.len();
+    let after_ws = line.trim_start_matches([' ', '\t']);
+    let after_slashes = after_ws.trim_start_matches('/');
+    let after_star = after_slashes.trim_start_matches('*');
+    let after_ws2 = after_star.trim_start_matches([' ', '\t']);
+    let content_offset = start + (line.len() - after_ws2.len());
+    (content_offset, after_ws2)
+  })
+}
+
+/// Gathers all `/// <reference types="..." />` directives in the file.
+fn collect_ts_references(
+  source: &str,
+  line_index: &LineIndex,
+  comments: &[Span],
+) -> Vec<TypeScriptReference> {
+  let mut references = Vec::new();
+  for &comment in comments {
+    let text = &source[comment.start as usize..comment.end as usize];
+    for (content_offset, content) in comment_line_contents(text) {
+      if !content.starts_with("<reference") {
+        continue;
+      }
+      let Some(types_idx) = content.find("types") else {
+        continue;
+      };
+      let quote_idx = content_offset + types_idx + "types".len();
+      let Some((value, value_start)) =
+        extract_quoted_value(text, comment.start as usize, quote_idx)
+      else {
+        continue;
+      };
+      let value_end = value_start + value.len();
+      references.push(TypeScriptReference::Types {
+        specifier: SpecifierWithRange {
+          text: value,
+          range: PositionRange {
+            start: line_index.position(source, value_start),
+            end: line_index.position(source, value_end),
+          },
+        },
+        resolution_mode: None,
+      });
+    }
+  }
+  references
+}
+
+/// Given `text` and a byte offset within it that should be followed (after
+/// an optional `=`) by a `"..."` or `'...'` value, returns the unquoted
+/// value and its absolute start offset within the whole source, where
+/// `text_start` is `text`'s own absolute offset in the source.
+fn extract_quoted_value(
+  text: &str,
+  text_start: usize,
+  from: usize,
+) -> Option<(String, usize)> {
+  let mut value_offset = from;
+  if text[value_offset..].starts_with('=') {
+    value_offset += 1;
+  }
+  let after = &text[value_offset..];
+  let quote = after.chars().next()?;
+  if quote != '"' && quote != '\'' {
+    return None;
+  }
+  let rest = &after[1..];
+  let end_idx = rest.find(quote)?;
+  let value = rest[..end_idx].to_string();
+  let value_start = text_start + value_offset + 1;
+  Some((value, value_start))
+}
+
+fn span_to_position_range(
+  source: &str,
+  line_index: &LineIndex,
+  span: Span,
+) -> PositionRange {
+  PositionRange {
+    start: line_index.position(source, span.start as usize),
+    end: line_index.position(source, span.end as usize),
+  }
+}
+
+/// A byte-offset -> line-start index built once per file, used to turn byte
+/// offsets into LSP-style `Position`s (line + UTF-16 code-unit column) in
+/// O(log n) instead of rescanning the whole source for every span endpoint.
+struct LineIndex {
+  /// Byte offset of the start of each line, in ascending order.
+  line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+  fn new(source: &str) -> Self {
+    let mut line_starts = vec![0];
+    line_starts.extend(
+      source
+        .bytes()
+        .enumerate()
+        .filter(|(_, b)| *b == b'\n')
+        .map(|(i, _)| i + 1),
+    );
+    Self { line_starts }
+  }
+
+  fn position(&self, source: &str, byte_index: usize) -> Position {
+    let line = self.line_starts.partition_point(|&start| start <= byte_index)
+      - 1;
+    let line_start = self.line_starts[line];
+    // Clamp to a char boundary in case `byte_index` lands inside a
+    // multi-byte character.
+    let mut column_end = byte_index.min(source.len());
+    while column_end > line_start && !source.is_char_boundary(column_end) {
+      column_end -= 1;
+    }
+    let character = source[line_start..column_end]
+      .chars()
+      .map(char::len_utf16)
+      .sum();
+    Position { line, character }
   }
 }